@@ -16,6 +16,7 @@ use std::str;
 use std::sync::Arc;
 
 use crate::RT;
+use lance::dataset::refs::RefFormat;
 use pyo3::prelude::*;
 use pyo3::{
     exceptions::{PyIOError, PyValueError},
@@ -26,6 +27,19 @@ use pyo3::{
 
 use self::commit::PyCommitLock;
 
+/// Parse the optional `format` argument accepted by the ref-writing methods
+/// below, defaulting to [`RefFormat::Json`] when not given.
+fn parse_ref_format(format: Option<String>) -> PyResult<RefFormat> {
+    match format.as_deref() {
+        None | Some("json") => Ok(RefFormat::Json),
+        Some("cbor") => Ok(RefFormat::Cbor),
+        Some(other) => Err(PyValueError::new_err(format!(
+            "unknown ref format '{}', expected 'json' or 'cbor'",
+            other
+        ))),
+    }
+}
+
 /// Lance Dataset that will be wrapped by another class in Python
 #[pyclass(name = "_Tags", module = "_lib")]
 #[derive(Clone)]
@@ -65,28 +79,146 @@ impl Tags {
         })
     }
 
-    fn create(&mut self, tag: String, version: u64) -> PyResult<()> {
+    #[pyo3(signature = (tag, version, format=None))]
+    fn create(&mut self, tag: String, version: u64, format: Option<String>) -> PyResult<()> {
+        let format = parse_ref_format(format)?;
+        let mut new_self = self.ds.tags.as_ref().clone();
+        RT.block_on(
+            None,
+            new_self.create_with_format(tag.as_str(), version, format),
+        )?
+        .map_err(|err| match err {
+            lance::Error::NotFound { .. } => PyValueError::new_err(err.to_string()),
+            lance::Error::RefConflict { .. } => PyValueError::new_err(err.to_string()),
+            lance::Error::VersionNotFound { .. } => PyValueError::new_err(err.to_string()),
+            _ => PyIOError::new_err(err.to_string()),
+        })?;
+        self.tags = Arc::new(new_self);
+        Ok(())
+    }
+
+    fn delete(&mut self, tag: String) -> PyResult<()> {
         let mut new_self = self.ds.tags.as_ref().clone();
-        RT.block_on(None, new_self.create(tag.as_str(), version))?
+        RT.block_on(None, new_self.delete(tag.as_str()))?
             .map_err(|err| match err {
                 lance::Error::NotFound { .. } => PyValueError::new_err(err.to_string()),
-                lance::Error::RefConflict { .. } => PyValueError::new_err(err.to_string()),
-                lance::Error::VersionNotFound { .. } => PyValueError::new_err(err.to_string()),
+                lance::Error::RefNotFound { .. } => PyValueError::new_err(err.to_string()),
                 _ => PyIOError::new_err(err.to_string()),
             })?;
         self.tags = Arc::new(new_self);
         Ok(())
     }
 
-    fn delete(&mut self, tag: String) -> PyResult<()> {
+    #[pyo3(signature = (tag, version, format=None))]
+    fn update(&mut self, tag: String, version: u64, format: Option<String>) -> PyResult<()> {
+        let format = parse_ref_format(format)?;
         let mut new_self = self.ds.tags.as_ref().clone();
-        RT.block_on(None, new_self.delete(tag.as_str()))?
+        RT.block_on(
+            None,
+            new_self.update_with_format(tag.as_str(), version, format),
+        )?
+        .map_err(|err| match err {
+            lance::Error::NotFound { .. } => PyValueError::new_err(err.to_string()),
+            lance::Error::RefNotFound { .. } => PyValueError::new_err(err.to_string()),
+            lance::Error::VersionNotFound { .. } => PyValueError::new_err(err.to_string()),
+            _ => PyIOError::new_err(err.to_string()),
+        })?;
+        self.tags = Arc::new(new_self);
+        Ok(())
+    }
+
+    fn checkout(self_: PyRef<'_, Self>, ref_name: String) -> PyResult<LanceDataset> {
+        let new_ds = RT
+            .block_on(None, self_.ds.checkout(ref_name.as_str()))?
+            .map_err(|err| match err {
+                lance::Error::RefNotFound { .. } => PyValueError::new_err(err.to_string()),
+                _ => PyIOError::new_err(err.to_string()),
+            })?;
+        Ok(LanceDataset::new(new_ds))
+    }
+}
+
+/// Lance Dataset branches, refs kept apart from the tags above whose
+/// `create` moves an existing branch, that will be wrapped by another class
+/// in Python
+#[pyclass(name = "_Branches", module = "_lib")]
+#[derive(Clone)]
+pub struct Branches {
+    pub(crate) ds: Arc<LanceDataset>,
+}
+
+#[pymethods]
+impl Branches {
+    #[new]
+    fn new(dataset: LanceDataset) -> PyResult<Self> {
+        Ok(Self {
+            ds: Arc::new(dataset),
+        })
+    }
+
+    pub fn __copy__(&self) -> Self {
+        self.clone()
+    }
+
+    fn list(self_: PyRef<'_, Self>) -> PyResult<PyObject> {
+        let branches = self_
+            .ds
+            .branches
+            .list()
+            .map_err(|err| PyValueError::new_err(err.to_string()))?;
+        Python::with_gil(|py| {
+            let pybranches = PyDict::new(py);
+            for (k, v) in branches.iter() {
+                let dict = PyDict::new(py);
+                dict.set_item("version", v.version).unwrap();
+                dict.set_item("manifest_size", v.manifest_size).unwrap();
+                dict.to_object(py);
+                pybranches.set_item(k, dict).unwrap();
+            }
+            Ok(pybranches.to_object(py))
+        })
+    }
+
+    #[pyo3(signature = (branch, version, format=None))]
+    fn create(&mut self, branch: String, version: u64, format: Option<String>) -> PyResult<()> {
+        let format = parse_ref_format(format)?;
+        let mut new_self = self.ds.branches.as_ref().clone();
+        RT.block_on(
+            None,
+            new_self.create_with_format(branch.as_str(), version, format),
+        )?
+        .map_err(|err| match err {
+            lance::Error::NotFound { .. } => PyValueError::new_err(err.to_string()),
+            lance::Error::VersionNotFound { .. } => PyValueError::new_err(err.to_string()),
+            _ => PyIOError::new_err(err.to_string()),
+        })?;
+        let mut ds = self.ds.as_ref().clone();
+        ds.branches = Arc::new(new_self);
+        self.ds = Arc::new(ds);
+        Ok(())
+    }
+
+    fn delete(&mut self, branch: String) -> PyResult<()> {
+        let mut new_self = self.ds.branches.as_ref().clone();
+        RT.block_on(None, new_self.delete(branch.as_str()))?
             .map_err(|err| match err {
                 lance::Error::NotFound { .. } => PyValueError::new_err(err.to_string()),
                 lance::Error::RefNotFound { .. } => PyValueError::new_err(err.to_string()),
                 _ => PyIOError::new_err(err.to_string()),
             })?;
-        self.tags = Arc::new(new_self);
+        let mut ds = self.ds.as_ref().clone();
+        ds.branches = Arc::new(new_self);
+        self.ds = Arc::new(ds);
         Ok(())
     }
+
+    fn checkout(self_: PyRef<'_, Self>, ref_name: String) -> PyResult<LanceDataset> {
+        let new_ds = RT
+            .block_on(None, self_.ds.checkout(ref_name.as_str()))?
+            .map_err(|err| match err {
+                lance::Error::RefNotFound { .. } => PyValueError::new_err(err.to_string()),
+                _ => PyIOError::new_err(err.to_string()),
+            })?;
+        Ok(LanceDataset::new(new_ds))
+    }
 }
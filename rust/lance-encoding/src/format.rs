@@ -18,8 +18,8 @@ use pb::{
     array_encoding::ArrayEncoding as ArrayEncodingEnum,
     buffer::BufferType,
     nullable::{AllNull, NoNull, Nullability, SomeNull},
-    ArrayEncoding, Binary, Bitpacked, Dictionary, FixedSizeBinary, FixedSizeList, Flat, Fsst,
-    Nullable, PackedStruct,
+    ArrayEncoding, Binary, Bitpacked, Dictionary, FixedSizeBinary, FixedSizeList, Flat,
+    FrameOfReference, Fsst, Nullable, PackedStruct, RunLength,
 };
 
 use crate::encodings::physical::block_compress::CompressionScheme;
@@ -169,4 +169,45 @@ impl ProtobufUtils {
             }))),
         }
     }
+
+    /// A run-length encoding: `values` encodes one entry per run and
+    /// `run_lengths_buffer_index` points at a parallel buffer recording how
+    /// many consecutive rows each value repeats for.
+    ///
+    /// This only describes the on-disk encoding; as with the other variants
+    /// on this type, the encoder/decoder that produces and consumes pages in
+    /// this format lives in the physical encodings layer, not here.
+    pub fn rle_encoding(
+        values: ArrayEncoding,
+        run_lengths_buffer_index: u32,
+        num_runs: u32,
+    ) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::RunLength(Box::new(RunLength {
+                values: Some(Box::new(values)),
+                run_lengths: Some(pb::Buffer {
+                    buffer_index: run_lengths_buffer_index,
+                    buffer_type: BufferType::Page as i32,
+                }),
+                num_runs,
+            }))),
+        }
+    }
+
+    /// A frame-of-reference encoding for sorted/clustered integer columns:
+    /// each value is reconstructed as `base + residual[i]`, where the
+    /// residuals are bitpacked separately.
+    ///
+    /// This only describes the on-disk encoding; as with [`Self::rle_encoding`],
+    /// the encoder/decoder lives in the physical encodings layer, not here.
+    pub fn frame_of_reference_encoding(base: i64, residuals: ArrayEncoding) -> ArrayEncoding {
+        ArrayEncoding {
+            array_encoding: Some(ArrayEncodingEnum::FrameOfReference(Box::new(
+                FrameOfReference {
+                    base,
+                    residuals: Some(Box::new(residuals)),
+                },
+            ))),
+        }
+    }
 }
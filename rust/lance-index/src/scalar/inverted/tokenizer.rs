@@ -1,12 +1,20 @@
 // SPDX-License-Identifier: Apache-2.0
 // SPDX-FileCopyrightText: Copyright The Lance Authors
 
-use std::{env, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    env,
+    path::PathBuf,
+};
 
 use lance_core::{Error, Result};
 use serde::{Deserialize, Serialize};
 use snafu::location;
 
+use self::filters::{StopWordsDictFilter, SynonymFilter};
+
+mod filters;
+
 #[cfg(feature = "tokenizer-lindera")]
 mod lindera;
 
@@ -20,6 +28,8 @@ pub struct TokenizerConfig {
     /// - `simple`: splits tokens on whitespace and punctuation
     /// - `whitespace`: splits tokens on whitespace
     /// - `raw`: no tokenization
+    /// - `ngram/<min>/<max>`: splits tokens into all ngrams of sizes `min` to `max`
+    /// - `edge_ngram/<min>/<max>`: splits tokens into prefix ngrams of sizes `min` to `max`
     /// - `lindera/*`: Lindera tokenizer
     /// - `jieba/*`: Jieba tokenizer
     ///
@@ -46,6 +56,39 @@ pub struct TokenizerConfig {
 
     /// ascii folding
     ascii_folding: bool,
+
+    /// minimum ngram size, used by the `ngram`/`edge_ngram` base tokenizers
+    /// when the tokenizer name does not already specify a `<min>/<max>` range
+    #[serde(default = "default_ngram_length")]
+    ngram_min_length: usize,
+
+    /// maximum ngram size, used by the `ngram`/`edge_ngram` base tokenizers
+    /// when the tokenizer name does not already specify a `<min>/<max>` range
+    #[serde(default = "default_ngram_length")]
+    ngram_max_length: usize,
+
+    /// whether the ngram tokenizer should only emit prefix ngrams
+    /// (i.e. `edge_ngram` semantics)
+    #[serde(default)]
+    ngram_prefix_only: bool,
+
+    /// directory under [`language_model_home()`] containing a user-supplied
+    /// stop word list, one word per line, at `words.txt`
+    ///
+    /// this is applied in addition to `remove_stop_words`, so it can be used
+    /// standalone or to extend a language's built-in stop word list
+    #[serde(default)]
+    stop_words_dir: Option<String>,
+
+    /// directory under [`language_model_home()`] containing a user-supplied
+    /// synonym map at `synonyms.txt`, one entry per line in the form
+    /// `term: synonym1,synonym2,...`
+    ///
+    /// a matched token is expanded into its configured alternatives at both
+    /// index and query time, e.g. so `k8s` and `kubernetes` resolve to the
+    /// same postings
+    #[serde(default)]
+    synonyms_dir: Option<String>,
 }
 
 impl Default for TokenizerConfig {
@@ -64,6 +107,11 @@ impl TokenizerConfig {
             stem: false,
             remove_stop_words: false,
             ascii_folding: false,
+            ngram_min_length: 3,
+            ngram_max_length: 3,
+            ngram_prefix_only: false,
+            stop_words_dir: None,
+            synonyms_dir: None,
         }
     }
 
@@ -104,8 +152,38 @@ impl TokenizerConfig {
         self
     }
 
+    pub fn ngram_min_length(mut self, ngram_min_length: usize) -> Self {
+        self.ngram_min_length = ngram_min_length;
+        self
+    }
+
+    pub fn ngram_max_length(mut self, ngram_max_length: usize) -> Self {
+        self.ngram_max_length = ngram_max_length;
+        self
+    }
+
+    pub fn ngram_prefix_only(mut self, ngram_prefix_only: bool) -> Self {
+        self.ngram_prefix_only = ngram_prefix_only;
+        self
+    }
+
+    pub fn stop_words_dir(mut self, stop_words_dir: Option<String>) -> Self {
+        self.stop_words_dir = stop_words_dir;
+        self
+    }
+
+    pub fn synonyms_dir(mut self, synonyms_dir: Option<String>) -> Self {
+        self.synonyms_dir = synonyms_dir;
+        self
+    }
+
     pub fn build(&self) -> Result<tantivy::tokenizer::TextAnalyzer> {
-        let mut builder = build_base_tokenizer_builder(&self.base_tokenizer)?;
+        let mut builder = build_base_tokenizer_builder(
+            &self.base_tokenizer,
+            self.ngram_min_length,
+            self.ngram_max_length,
+            self.ngram_prefix_only,
+        )?;
         if let Some(max_token_length) = self.max_token_length {
             builder = builder.filter_dynamic(tantivy::tokenizer::RemoveLongFilter::limit(
                 max_token_length,
@@ -133,11 +211,86 @@ impl TokenizerConfig {
         if self.ascii_folding {
             builder = builder.filter_dynamic(tantivy::tokenizer::AsciiFoldingFilter);
         }
+        if let Some(dir) = &self.stop_words_dir {
+            builder = builder.filter_dynamic(StopWordsDictFilter::new(load_word_list(dir)?));
+        }
+        if let Some(dir) = &self.synonyms_dir {
+            builder = builder.filter_dynamic(SynonymFilter::new(load_synonym_map(dir)?));
+        }
         Ok(builder.build())
     }
 }
 
-fn build_base_tokenizer_builder(name: &str) -> Result<tantivy::tokenizer::TextAnalyzerBuilder> {
+const STOP_WORDS_FILE: &str = "words.txt";
+
+const SYNONYMS_FILE: &str = "synonyms.txt";
+
+fn language_model_file(dir: &str, file_name: &str) -> Result<PathBuf> {
+    let Some(home) = language_model_home() else {
+        return Err(Error::invalid_input(
+            format!("cannot resolve language model directory {}", dir),
+            location!(),
+        ));
+    };
+    Ok(home.join(dir).join(file_name))
+}
+
+// loads a newline-separated word list, ignoring blank lines, reusing the
+// `language_model_home()`-relative directory convention of the base
+// tokenizers such as `lindera/*` and `jieba/*`
+fn load_word_list(dir: &str) -> Result<HashSet<String>> {
+    let path = language_model_file(dir, STOP_WORDS_FILE)?;
+    let content = std::fs::read_to_string(&path)?;
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_owned)
+        .collect())
+}
+
+// loads a synonym map of the form `term: synonym1,synonym2,...`, one entry
+// per line, ignoring blank lines
+fn load_synonym_map(dir: &str) -> Result<HashMap<String, Vec<String>>> {
+    let path = language_model_file(dir, SYNONYMS_FILE)?;
+    let content = std::fs::read_to_string(&path)?;
+
+    let mut synonyms = HashMap::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let Some((term, alternatives)) = line.split_once(':') else {
+            return Err(Error::invalid_input(
+                format!("invalid synonym entry {}, expected term: synonym1,synonym2,...", line),
+                location!(),
+            ));
+        };
+        let alternatives = alternatives
+            .split(',')
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .map(str::to_owned)
+            .collect();
+        synonyms.insert(term.trim().to_owned(), alternatives);
+    }
+    Ok(synonyms)
+}
+
+// matches the ngram defaults used by `TokenizerConfig::new`, so that
+// `TokenizerConfig`s serialized before these fields existed keep behaving
+// the same way once deserialized
+fn default_ngram_length() -> usize {
+    3
+}
+
+fn build_base_tokenizer_builder(
+    name: &str,
+    default_ngram_min_length: usize,
+    default_ngram_max_length: usize,
+    default_ngram_prefix_only: bool,
+) -> Result<tantivy::tokenizer::TextAnalyzerBuilder> {
     match name {
         "simple" => Ok(tantivy::tokenizer::TextAnalyzer::builder(
             tantivy::tokenizer::SimpleTokenizer::default(),
@@ -151,6 +304,26 @@ fn build_base_tokenizer_builder(name: &str) -> Result<tantivy::tokenizer::TextAn
             tantivy::tokenizer::RawTokenizer::default(),
         )
         .dynamic()),
+        s if s == "ngram" || s.starts_with("ngram/") => {
+            let (min, max) = parse_ngram_range(s, "ngram")?
+                .unwrap_or((default_ngram_min_length, default_ngram_max_length));
+            let tokenizer = tantivy::tokenizer::NgramTokenizer::new(
+                min,
+                max,
+                default_ngram_prefix_only,
+            )
+            .map_err(|e| Error::invalid_input(e.to_string(), location!()))?;
+            Ok(tantivy::tokenizer::TextAnalyzer::builder(tokenizer).dynamic())
+        }
+        // `edge_ngram` is simply `ngram` with prefix-only matching forced on,
+        // regardless of the `ngram_prefix_only` config field
+        s if s == "edge_ngram" || s.starts_with("edge_ngram/") => {
+            let (min, max) = parse_ngram_range(s, "edge_ngram")?
+                .unwrap_or((default_ngram_min_length, default_ngram_max_length));
+            let tokenizer = tantivy::tokenizer::NgramTokenizer::new(min, max, true)
+                .map_err(|e| Error::invalid_input(e.to_string(), location!()))?;
+            Ok(tantivy::tokenizer::TextAnalyzer::builder(tokenizer).dynamic())
+        }
         #[cfg(feature = "tokenizer-lindera")]
         s if s.starts_with("lindera/") => {
             let Some(home) = language_model_home() else {
@@ -179,6 +352,44 @@ fn build_base_tokenizer_builder(name: &str) -> Result<tantivy::tokenizer::TextAn
     }
 }
 
+// Parses a tokenizer name of the form `<prefix>/<min>/<max>`, returning
+// `Ok(None)` when the name is the bare prefix (e.g. `ngram`), so the caller
+// can fall back to its own defaults.
+fn parse_ngram_range(name: &str, prefix: &str) -> Result<Option<(usize, usize)>> {
+    if name == prefix {
+        return Ok(None);
+    }
+
+    let rest = name.strip_prefix(prefix).and_then(|s| s.strip_prefix('/'));
+    let Some(rest) = rest else {
+        return Err(Error::invalid_input(
+            format!("unknown base tokenizer {}", name),
+            location!(),
+        ));
+    };
+
+    let parse_err = || {
+        Error::invalid_input(
+            format!(
+                "invalid {} tokenizer name {}, expected {}/<min>/<max>",
+                prefix, name, prefix
+            ),
+            location!(),
+        )
+    };
+
+    let mut parts = rest.split('/');
+    let min = parts.next().ok_or_else(parse_err)?;
+    let max = parts.next().ok_or_else(parse_err)?;
+    if parts.next().is_some() {
+        return Err(parse_err());
+    }
+
+    let min = min.parse::<usize>().map_err(|_| parse_err())?;
+    let max = max.parse::<usize>().map_err(|_| parse_err())?;
+    Ok(Some((min, max)))
+}
+
 pub const LANCE_LANGUAGE_MODEL_HOME_ENV_KEY: &str = "LANCE_LANGUAGE_MODEL_HOME";
 
 pub const LANCE_LANGUAGE_MODEL_DEFAULT_DIRECTORY: &str = "lance/language_models";
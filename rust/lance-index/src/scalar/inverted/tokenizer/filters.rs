@@ -0,0 +1,162 @@
+// SPDX-License-Identifier: Apache-2.0
+// SPDX-FileCopyrightText: Copyright The Lance Authors
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::Arc;
+
+use tantivy::tokenizer::{Token, TokenFilter, TokenStream, Tokenizer};
+
+/// A [`TokenFilter`] that drops tokens found in a user-supplied dictionary,
+/// in addition to (or instead of) tantivy's built-in, language-specific
+/// [`tantivy::tokenizer::StopWordFilter`].
+#[derive(Clone)]
+pub struct StopWordsDictFilter {
+    words: Arc<HashSet<String>>,
+}
+
+impl StopWordsDictFilter {
+    pub fn new(words: HashSet<String>) -> Self {
+        Self {
+            words: Arc::new(words),
+        }
+    }
+}
+
+impl TokenFilter for StopWordsDictFilter {
+    type Tokenizer<T: Tokenizer> = StopWordsDictFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> Self::Tokenizer<T> {
+        StopWordsDictFilterWrapper {
+            words: self.words,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct StopWordsDictFilterWrapper<T> {
+    words: Arc<HashSet<String>>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for StopWordsDictFilterWrapper<T> {
+    type TokenStream<'a> = StopWordsDictFilterStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        StopWordsDictFilterStream {
+            words: self.words.clone(),
+            tail: self.inner.token_stream(text),
+        }
+    }
+}
+
+pub struct StopWordsDictFilterStream<T> {
+    words: Arc<HashSet<String>>,
+    tail: T,
+}
+
+impl<T: TokenStream> TokenStream for StopWordsDictFilterStream<T> {
+    fn advance(&mut self) -> bool {
+        while self.tail.advance() {
+            if !self.words.contains(&self.tail.token().text) {
+                return true;
+            }
+        }
+        false
+    }
+
+    fn token(&self) -> &Token {
+        self.tail.token()
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        self.tail.token_mut()
+    }
+}
+
+/// A [`TokenFilter`] that expands a matched token into its configured
+/// synonyms, emitting the synonyms as additional tokens at the same
+/// position as the original so that either form matches at index and query
+/// time (e.g. `k8s` and `kubernetes` resolve to the same postings).
+#[derive(Clone)]
+pub struct SynonymFilter {
+    synonyms: Arc<HashMap<String, Vec<String>>>,
+}
+
+impl SynonymFilter {
+    pub fn new(synonyms: HashMap<String, Vec<String>>) -> Self {
+        Self {
+            synonyms: Arc::new(synonyms),
+        }
+    }
+}
+
+impl TokenFilter for SynonymFilter {
+    type Tokenizer<T: Tokenizer> = SynonymFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> Self::Tokenizer<T> {
+        SynonymFilterWrapper {
+            synonyms: self.synonyms,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SynonymFilterWrapper<T> {
+    synonyms: Arc<HashMap<String, Vec<String>>>,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for SynonymFilterWrapper<T> {
+    type TokenStream<'a> = SynonymFilterStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        SynonymFilterStream {
+            synonyms: self.synonyms.clone(),
+            tail: self.inner.token_stream(text),
+            queue: VecDeque::new(),
+            current: Token::default(),
+        }
+    }
+}
+
+pub struct SynonymFilterStream<T> {
+    synonyms: Arc<HashMap<String, Vec<String>>>,
+    tail: T,
+    // pending synonym tokens for the word the tail stream is currently on,
+    // emitted at the same position as that word
+    queue: VecDeque<Token>,
+    current: Token,
+}
+
+impl<T: TokenStream> TokenStream for SynonymFilterStream<T> {
+    fn advance(&mut self) -> bool {
+        if let Some(queued) = self.queue.pop_front() {
+            self.current = queued;
+            return true;
+        }
+
+        if !self.tail.advance() {
+            return false;
+        }
+
+        self.current = self.tail.token().clone();
+        if let Some(alternatives) = self.synonyms.get(&self.current.text) {
+            for alternative in alternatives {
+                let mut synonym_token = self.current.clone();
+                synonym_token.text = alternative.clone();
+                self.queue.push_back(synonym_token);
+            }
+        }
+        true
+    }
+
+    fn token(&self) -> &Token {
+        &self.current
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.current
+    }
+}
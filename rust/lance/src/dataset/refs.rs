@@ -13,6 +13,17 @@ pub struct TagContents {
     pub manifest_size: usize,
 }
 
+/// On-disk encoding for a ref's `TagContents`.
+///
+/// `Json` is the legacy, human-readable format. `Cbor` is a compact binary
+/// encoding for writers that want to avoid the cost of string parsing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RefFormat {
+    #[default]
+    Json,
+    Cbor,
+}
+
 pub fn base_tags_path(base_path: &Path) -> Path {
     base_path.child("_refs").child("tags")
 }
@@ -21,6 +32,14 @@ pub fn tag_path(base_path: &Path, tag: &str) -> Path {
     base_tags_path(base_path).child(format!("{}.json", tag))
 }
 
+pub fn base_heads_path(base_path: &Path) -> Path {
+    base_path.child("_refs").child("heads")
+}
+
+pub fn head_path(base_path: &Path, branch: &str) -> Path {
+    base_heads_path(base_path).child(format!("{}.json", branch))
+}
+
 pub fn check_valid_ref(s: &str) -> Result<()> {
     if s.is_empty() {
         return Err(Error::InvalidRef {
@@ -92,6 +111,13 @@ pub fn check_valid_ref(s: &str) -> Result<()> {
 }
 
 impl TagContents {
+    pub fn new(version: u64, manifest_size: usize) -> Self {
+        Self {
+            version,
+            manifest_size,
+        }
+    }
+
     pub async fn from_path(path: &Path, object_store: &ObjectStore) -> Result<Self> {
         let tag_reader = object_store.open(path).await?;
         let tag_bytes = tag_reader
@@ -100,9 +126,42 @@ impl TagContents {
                 end: tag_reader.size().await?,
             })
             .await?;
-        Ok(serde_json::from_str(
-            String::from_utf8(tag_bytes.to_vec()).unwrap().as_str(),
-        )?)
+
+        // A CBOR map header is major type 5 (byte 0xA0-0xBF), which can never
+        // be the first byte of a valid JSON document, so sniffing the first
+        // byte is enough to unambiguously tell the two formats apart.
+        match tag_bytes.first() {
+            Some(0xA0..=0xBF) => Ok(ciborium::from_reader(tag_bytes.as_ref())
+                .map_err(|e| Error::Internal {
+                    message: format!("failed to parse CBOR ref contents: {}", e),
+                })?),
+            _ => {
+                let tag_str = std::str::from_utf8(&tag_bytes).map_err(|e| Error::Internal {
+                    message: format!("ref contents are not valid UTF-8: {}", e),
+                })?;
+                Ok(serde_json::from_str(tag_str)?)
+            }
+        }
+    }
+
+    pub async fn write_to(
+        &self,
+        path: &Path,
+        object_store: &ObjectStore,
+        format: RefFormat,
+    ) -> Result<()> {
+        let content = match format {
+            RefFormat::Json => serde_json::to_vec_pretty(self)?,
+            RefFormat::Cbor => {
+                let mut buf = Vec::new();
+                ciborium::into_writer(self, &mut buf).map_err(|e| Error::Internal {
+                    message: format!("failed to serialize CBOR ref contents: {}", e),
+                })?;
+                buf
+            }
+        };
+        object_store.put(path, &content).await?;
+        Ok(())
     }
 }
 
@@ -153,4 +212,18 @@ mod tests {
             "Ref is invalid. Ref must confirm to git ref formatting rules"
         );
     }
+
+    #[rstest]
+    #[tokio::test]
+    async fn test_tag_contents_roundtrip(#[values(RefFormat::Json, RefFormat::Cbor)] format: RefFormat) {
+        let object_store = ObjectStore::memory();
+        let path = Path::from("_refs/tags/v1.json");
+        let contents = TagContents::new(1, 100);
+
+        contents.write_to(&path, &object_store, format).await.unwrap();
+
+        let roundtripped = TagContents::from_path(&path, &object_store).await.unwrap();
+        assert_eq!(roundtripped.version, contents.version);
+        assert_eq!(roundtripped.manifest_size, contents.manifest_size);
+    }
 }
@@ -1,7 +1,256 @@
-use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TagContents {
-    pub version: u64,
-    pub manifest_size: usize,
+use super::refs::{
+    base_heads_path, base_tags_path, check_valid_ref, head_path, tag_path, RefFormat, TagContents,
+};
+use super::Dataset;
+use crate::{Error, Result};
+
+/// Tags associate a version number with a human readable name. Once created,
+/// a tag keeps pointing at its version until it is explicitly repointed
+/// with [`Tags::update`]; [`Tags::create`] never moves an existing tag.
+///
+/// Tags can be used to retrieve a version of a dataset by a human readable
+/// name, or with [`Dataset::checkout`] to load the dataset at the tagged
+/// version.
+#[derive(Debug, Clone)]
+pub struct Tags {
+    dataset: Arc<Dataset>,
+}
+
+impl Tags {
+    pub(crate) fn new(dataset: Arc<Dataset>) -> Self {
+        Self { dataset }
+    }
+
+    /// List all tags, mapping tag name to the version and manifest size it
+    /// points at.
+    pub async fn list(&self) -> Result<HashMap<String, TagContents>> {
+        let object_store = self.dataset.object_store();
+        let tags_path = base_tags_path(&self.dataset.base);
+
+        let mut tags = HashMap::new();
+        for tag_file in object_store.read_dir(&tags_path).await? {
+            let Some(tag_name) = tag_file.strip_suffix(".json") else {
+                continue;
+            };
+            let contents = TagContents::from_path(&tag_path(&self.dataset.base, tag_name), object_store).await?;
+            tags.insert(tag_name.to_string(), contents);
+        }
+        Ok(tags)
+    }
+
+    /// Get the version a tag currently points at.
+    pub async fn get_version(&self, tag: &str) -> Result<u64> {
+        check_valid_ref(tag)?;
+        let contents =
+            TagContents::from_path(&tag_path(&self.dataset.base, tag), self.dataset.object_store())
+                .await?;
+        Ok(contents.version)
+    }
+
+    /// Create a new tag pointing at `version`, encoded on disk as JSON.
+    /// Fails if the tag already exists or if `version` does not exist.
+    pub async fn create(&mut self, tag: &str, version: u64) -> Result<()> {
+        self.create_with_format(tag, version, RefFormat::Json).await
+    }
+
+    /// Like [`Tags::create`], but lets the caller choose the on-disk
+    /// encoding (e.g. [`RefFormat::Cbor`] to avoid the cost of JSON string
+    /// parsing for new writers).
+    pub async fn create_with_format(
+        &mut self,
+        tag: &str,
+        version: u64,
+        format: RefFormat,
+    ) -> Result<()> {
+        check_valid_ref(tag)?;
+
+        let object_store = self.dataset.object_store();
+        let path = tag_path(&self.dataset.base, tag);
+        if object_store.exists(&path).await? {
+            return Err(Error::RefConflict {
+                message: format!("tag {} already exists", tag),
+            });
+        }
+
+        let manifest_size = self
+            .dataset
+            .manifest_location_for_version(version)
+            .await?
+            .size;
+        let contents = TagContents::new(version, manifest_size);
+        contents.write_to(&path, object_store, format).await?;
+
+        Ok(())
+    }
+
+    /// Atomically repoint an existing tag at a different `version`,
+    /// encoded on disk as JSON. Unlike [`Tags::create`], this overwrites the
+    /// tag's current contents rather than failing when the tag already
+    /// exists.
+    pub async fn update(&mut self, tag: &str, version: u64) -> Result<()> {
+        self.update_with_format(tag, version, RefFormat::Json).await
+    }
+
+    /// Like [`Tags::update`], but lets the caller choose the on-disk
+    /// encoding (e.g. [`RefFormat::Cbor`] to avoid the cost of JSON string
+    /// parsing for new writers).
+    pub async fn update_with_format(
+        &mut self,
+        tag: &str,
+        version: u64,
+        format: RefFormat,
+    ) -> Result<()> {
+        check_valid_ref(tag)?;
+
+        let object_store = self.dataset.object_store();
+        let path = tag_path(&self.dataset.base, tag);
+        if !object_store.exists(&path).await? {
+            return Err(Error::RefNotFound {
+                message: format!("tag {} does not exist", tag),
+            });
+        }
+
+        let manifest_size = self
+            .dataset
+            .manifest_location_for_version(version)
+            .await?
+            .size;
+        let contents = TagContents::new(version, manifest_size);
+        contents.write_to(&path, object_store, format).await?;
+
+        Ok(())
+    }
+
+    /// Delete a tag.
+    pub async fn delete(&mut self, tag: &str) -> Result<()> {
+        check_valid_ref(tag)?;
+
+        let object_store = self.dataset.object_store();
+        let path = tag_path(&self.dataset.base, tag);
+        if !object_store.exists(&path).await? {
+            return Err(Error::RefNotFound {
+                message: format!("tag {} does not exist", tag),
+            });
+        }
+
+        object_store.delete(&path).await?;
+        Ok(())
+    }
+}
+
+/// Branches are mutable refs stored under `_refs/heads/<branch>.json`. Unlike
+/// a tag, a branch is expected to be repointed over time as new versions are
+/// written, similar to a git branch head.
+#[derive(Debug, Clone)]
+pub struct Branches {
+    dataset: Arc<Dataset>,
+}
+
+impl Branches {
+    pub(crate) fn new(dataset: Arc<Dataset>) -> Self {
+        Self { dataset }
+    }
+
+    pub async fn list(&self) -> Result<HashMap<String, TagContents>> {
+        let object_store = self.dataset.object_store();
+        let heads_path = base_heads_path(&self.dataset.base);
+
+        let mut branches = HashMap::new();
+        for head_file in object_store.read_dir(&heads_path).await? {
+            let Some(branch_name) = head_file.strip_suffix(".json") else {
+                continue;
+            };
+            let contents =
+                TagContents::from_path(&head_path(&self.dataset.base, branch_name), object_store)
+                    .await?;
+            branches.insert(branch_name.to_string(), contents);
+        }
+        Ok(branches)
+    }
+
+    /// Create a new branch pointing at `version`, encoded on disk as JSON,
+    /// or move it if it already exists.
+    pub async fn create(&mut self, branch: &str, version: u64) -> Result<()> {
+        self.create_with_format(branch, version, RefFormat::Json)
+            .await
+    }
+
+    /// Like [`Branches::create`], but lets the caller choose the on-disk
+    /// encoding (e.g. [`RefFormat::Cbor`] to avoid the cost of JSON string
+    /// parsing for new writers).
+    pub async fn create_with_format(
+        &mut self,
+        branch: &str,
+        version: u64,
+        format: RefFormat,
+    ) -> Result<()> {
+        check_valid_ref(branch)?;
+
+        let object_store = self.dataset.object_store();
+        let path = head_path(&self.dataset.base, branch);
+        let manifest_size = self
+            .dataset
+            .manifest_location_for_version(version)
+            .await?
+            .size;
+        let contents = TagContents::new(version, manifest_size);
+        contents.write_to(&path, object_store, format).await?;
+
+        Ok(())
+    }
+
+    pub async fn delete(&mut self, branch: &str) -> Result<()> {
+        check_valid_ref(branch)?;
+
+        let object_store = self.dataset.object_store();
+        let path = head_path(&self.dataset.base, branch);
+        if !object_store.exists(&path).await? {
+            return Err(Error::RefNotFound {
+                message: format!("branch {} does not exist", branch),
+            });
+        }
+
+        object_store.delete(&path).await?;
+        Ok(())
+    }
+}
+
+/// Resolve a ref name to a version, checking branches before tags so that a
+/// branch can shadow a tag of the same name.
+pub(crate) async fn resolve_ref(dataset: &Dataset, ref_name: &str) -> Result<u64> {
+    check_valid_ref(ref_name)?;
+
+    let object_store = dataset.object_store();
+
+    let head_path = head_path(&dataset.base, ref_name);
+    if object_store.exists(&head_path).await? {
+        return Ok(TagContents::from_path(&head_path, object_store)
+            .await?
+            .version);
+    }
+
+    let tag_path = tag_path(&dataset.base, ref_name);
+    if object_store.exists(&tag_path).await? {
+        return Ok(TagContents::from_path(&tag_path, object_store)
+            .await?
+            .version);
+    }
+
+    Err(Error::RefNotFound {
+        message: format!("ref {} does not exist", ref_name),
+    })
+}
+
+impl Dataset {
+    /// Check out the dataset at the version pointed at by `ref_name`, which
+    /// may be either a branch or a tag. Branches take precedence over tags
+    /// when the names collide, since branches are the more actively moving
+    /// ref.
+    pub async fn checkout(&self, ref_name: &str) -> Result<Self> {
+        let version = resolve_ref(self, ref_name).await?;
+        self.checkout_version(version).await
+    }
 }